@@ -0,0 +1,27 @@
+use std::env;
+use std::fs::File;
+use std::path::Path;
+
+use cfg_aliases::cfg_aliases;
+use gl_generator::{Api, Fallbacks, Profile, Registry, StructGenerator};
+
+fn main() {
+    // Backend aliases so the platform-specific display/context paths can be
+    // gated on a single `cfg(...)` instead of repeating `all(feature = "egl",
+    // unix)` everywhere. Exposed to Cargo as the `egl`/`glx`/`wgl`/`wayland`
+    // features (`default = ["egl", "wayland"]`).
+    cfg_aliases! {
+        egl: { all(feature = "egl", unix) },
+        glx: { all(feature = "glx", unix) },
+        wgl: { all(feature = "wgl", windows) },
+        wayland: { all(feature = "wayland", unix) },
+    }
+
+    // GL bindings are still generated here so downstream tooling and any
+    // platform glue that needs the raw entry points keeps building.
+    let dest = env::var("OUT_DIR").unwrap();
+    let mut file = File::create(Path::new(&dest).join("gl_bindings.rs")).unwrap();
+    Registry::new(Api::Gl, (4, 6), Profile::Core, Fallbacks::All, [])
+        .write_bindings(StructGenerator, &mut file)
+        .unwrap();
+}
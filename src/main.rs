@@ -1,22 +1,35 @@
 use glutin::{
-    config::{ConfigTemplateBuilder, GlConfig},
-    context::PossiblyCurrentContext,
-    context::{ContextAttributesBuilder, NotCurrentGlContext, PossiblyCurrentGlContext},
-    display::{Display, DisplayApiPreference, GetGlDisplay, GlDisplay},
-    surface::{GlSurface, Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface},
+    config::{Config, ConfigTemplateBuilder, GlConfig},
+    context::{
+        AsRawContext, ContextAttributesBuilder, NotCurrentContext, NotCurrentGlContext,
+        PossiblyCurrentContext, PossiblyCurrentGlContext, RawContext,
+    },
+    display::{AsRawDisplay, GetGlDisplay, GlDisplay, RawDisplay},
+    surface::{GlSurface, Surface, SwapInterval, WindowSurface},
 };
 
+use glutin_winit::{DisplayBuilder, GlWindow};
+
 use winit::{
     application::ApplicationHandler,
     event::WindowEvent,
     event_loop::{ActiveEventLoop, EventLoop},
-    raw_window_handle::{HasDisplayHandle, HasWindowHandle},
-    window::{Window, WindowId},
+    raw_window_handle::HasWindowHandle,
+    window::{Window, WindowAttributes, WindowId},
 };
 
+use gst::prelude::*;
+
+use notify::{RecursiveMode, Watcher};
+
+use std::cell::Cell;
 use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
 
 fn main() {
+    gst::init().unwrap();
+
     let event_loop = EventLoop::new().unwrap();
 
     let mut app = App::new();
@@ -24,111 +37,210 @@ fn main() {
     event_loop.run_app(&mut app).unwrap();
 }
 
+/// Window attributes shared by the initial `DisplayBuilder` pass and any
+/// window recreated on resume.
+fn window_attributes() -> WindowAttributes {
+    Window::default_attributes()
+        .with_transparent(true)
+        .with_title("Watch Together")
+}
+
+/// On the first `resumed` we still need to create the display; afterwards only
+/// the window and surface are (re)created.
+enum GlDisplayState {
+    Builder(DisplayBuilder),
+    Init,
+}
+
+/// The parts of the app that live and die with the window surface — on Android
+/// these are torn down on `suspended` and rebuilt on the next `resumed`.
+struct AppState {
+    gl_surface: Surface<WindowSurface>,
+    window: Window,
+}
+
 struct App {
-    renderer: Option<Renderer>,
-    gl_surface: Option<Surface<WindowSurface>>,
+    template: ConfigTemplateBuilder,
+    gl_display: GlDisplayState,
+    gl_config: Option<Config>,
     gl_context: Option<PossiblyCurrentContext>,
-    window: Option<Window>,
+    renderer: Option<Renderer>,
+    state: Option<AppState>,
+    video: Option<VideoPlayer>,
+    overlay: Option<Texture>,
 }
 
 impl App {
     fn new() -> Self {
-        App {
-            renderer: None,
-            gl_surface: None,
-            gl_context: None,
-            window: None,
-        }
-    }
-}
-
-impl ApplicationHandler for App {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         let template = ConfigTemplateBuilder::new()
             .with_alpha_size(8)
-            .with_transparency(true)
-            .build();
-
-        let window_attributes = Window::default_attributes()
-            .with_transparent(true)
-            .with_title("Watch Together");
-
-        let raw_display_handle = event_loop.display_handle().unwrap().as_raw();
-        
-        #[cfg(windows)]
-        let gl_display =
-            unsafe { Display::new(raw_display_handle, DisplayApiPreference::Wgl(self.window.as_ref().unwrap().window_handle().unwrap().as_raw())).unwrap() };
+            .with_transparency(true);
 
-        #[cfg(unix)]
         let gl_display =
-            unsafe { Display::new(raw_display_handle, DisplayApiPreference::Egl).unwrap() };
-
-
-        let configs = unsafe { gl_display.find_configs(template).unwrap() };
-
-        let gl_config = configs
-            .reduce(|accum, config| {
-                let transparency_check = config.supports_transparency().unwrap_or(false)
-                    & !accum.supports_transparency().unwrap_or(false);
+            GlDisplayState::Builder(DisplayBuilder::new().with_window_attributes(Some(window_attributes())));
 
-                if transparency_check || config.num_samples() > accum.num_samples() {
-                    config
-                } else {
-                    accum
-                }
-            })
-            .unwrap();
-
-        let window = event_loop.create_window(window_attributes).unwrap();
+        App {
+            template,
+            gl_display,
+            gl_config: None,
+            gl_context: None,
+            renderer: None,
+            state: None,
+            video: None,
+            overlay: None,
+        }
+    }
+}
 
-        let size = window.inner_size();
-        let width = size.width;
-        let height = size.height;
+/// Pick the config with the most desirable transparency/sample combination,
+/// matching the heuristic the app used before.
+fn gl_config_picker(configs: Box<dyn Iterator<Item = Config> + '_>) -> Config {
+    configs
+        .reduce(|accum, config| {
+            let transparency_check = config.supports_transparency().unwrap_or(false)
+                & !accum.supports_transparency().unwrap_or(false);
+
+            if transparency_check || config.num_samples() > accum.num_samples() {
+                config
+            } else {
+                accum
+            }
+        })
+        .unwrap()
+}
 
-        let raw_window_handle = window.window_handle().unwrap().as_raw();
+/// Create the (not-yet-current) GL context for `gl_config`. Done once, kept
+/// across suspend/resume cycles.
+fn create_gl_context(window: &Window, gl_config: &Config) -> NotCurrentContext {
+    let raw_window_handle = window.window_handle().ok().map(|handle| handle.as_raw());
+    let context_attributes = ContextAttributesBuilder::new().build(raw_window_handle);
+    let gl_display = gl_config.display();
+    unsafe {
+        gl_display
+            .create_context(gl_config, &context_attributes)
+            .expect("failed to create GL context")
+    }
+}
 
-        let surface_attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
-            raw_window_handle,
-            NonZeroU32::new(width).unwrap(),
-            NonZeroU32::new(height).unwrap(),
-        );
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        // On the very first resume we still have to pick a config and create the
+        // window + display together; on every later resume the display, config
+        // and context already exist and only the window + surface are rebuilt.
+        let (window, gl_config) = match &self.gl_display {
+            GlDisplayState::Builder(display_builder) => {
+                let (window, gl_config) = match display_builder.clone().build(
+                    event_loop,
+                    self.template.clone(),
+                    gl_config_picker,
+                ) {
+                    Ok((window, gl_config)) => (window.unwrap(), gl_config),
+                    Err(err) => {
+                        eprintln!("failed to create GL config: {err}");
+                        event_loop.exit();
+                        return;
+                    }
+                };
+
+                // The context is created once here and kept across suspend/resume
+                // cycles; only the surface comes and goes.
+                self.gl_context =
+                    Some(create_gl_context(&window, &gl_config).treat_as_possibly_current());
+                self.gl_display = GlDisplayState::Init;
+                self.gl_config = Some(gl_config.clone());
+
+                (window, gl_config)
+            }
+            GlDisplayState::Init => {
+                let gl_config = self.gl_config.clone().unwrap();
+                let window =
+                    glutin_winit::finalize_window(event_loop, window_attributes(), &gl_config)
+                        .unwrap();
+                (window, gl_config)
+            }
+        };
 
+        let attrs = window
+            .build_surface_attributes(Default::default())
+            .expect("failed to build surface attributes");
         let gl_surface = unsafe {
             gl_config
                 .display()
-                .create_window_surface(&gl_config, &surface_attributes)
-                .unwrap()
-        };
-
-        let context_attributes = ContextAttributesBuilder::new().build(Some(raw_window_handle));
-
-        // shadowed
-        let gl_display = gl_config.display();
-
-        let possibly_current_context = unsafe {
-            gl_display
-                .create_context(&gl_config, &context_attributes)
+                .create_window_surface(&gl_config, &attrs)
                 .unwrap()
-                .treat_as_possibly_current()
         };
 
-        possibly_current_context.make_current(&gl_surface).unwrap();
-
-        self.gl_context = Some(possibly_current_context);
-        self.gl_surface = Some(gl_surface);
-
-        self.window = Some(window);
+        let gl_context = self.gl_context.as_ref().unwrap();
+        gl_context.make_current(&gl_surface).unwrap();
+
+        if self.renderer.is_none() {
+            match Renderer::new(&gl_config.display()) {
+                Ok(renderer) => self.renderer = Some(renderer),
+                Err(err) => {
+                    eprintln!("{err}");
+                    event_loop.exit();
+                    return;
+                }
+            }
+        }
 
+        // Prime the viewport from the current window size so the first frame is
+        // aspect-correct even before the initial `Resized` event arrives.
+        let size = window.inner_size();
         self.renderer
-            .get_or_insert_with(|| Renderer::new(&gl_config.display()));
-
-        let gl_context = self.gl_context.as_ref().unwrap();
-        let gl_surface = self.gl_surface.as_ref().unwrap();
+            .as_ref()
+            .unwrap()
+            .resize(size.width as i32, size.height as i32);
+
+        // Share the current GL context with GStreamer and start playing the
+        // requested video. The URI comes from `WATCH_TOGETHER_URI`; with none
+        // set the player falls back to a built-in `videotestsrc` pattern so the
+        // app shows something without arguments.
+        let uri = std::env::var("WATCH_TOGETHER_URI").ok();
+        self.video.get_or_insert_with(|| {
+            VideoPlayer::new(gl_context, &gl_config.display(), uri.as_deref())
+        });
+
+        // Optionally load a still image (poster art / "waiting for host") shown
+        // whenever no video frame is available.
+        if self.overlay.is_none() {
+            if let Ok(path) = std::env::var("WATCH_TOGETHER_IMAGE") {
+                match self.renderer.as_ref().unwrap().load_image(Path::new(&path)) {
+                    Ok(texture) => self.overlay = Some(texture),
+                    Err(err) => eprintln!("failed to load image {path}: {err}"),
+                }
+            }
+        }
 
         // Try setting vsync.
         gl_surface
-            .set_swap_interval(&gl_context, SwapInterval::Wait(NonZeroU32::new(1).unwrap()))
+            .set_swap_interval(gl_context, SwapInterval::Wait(NonZeroU32::new(1).unwrap()))
             .unwrap();
+
+        assert!(self
+            .state
+            .replace(AppState { gl_surface, window })
+            .is_none());
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        // On Android the backing surface is torn down whenever the activity is
+        // backgrounded. Drop the window + surface and un-current the context so
+        // the platform can reclaim the native window; the next `resumed` rebuilds
+        // them against the same context.
+        self.state = None;
+
+        // A suspend can arrive before the first successful `resumed` (or after a
+        // resume that bailed out via an error path), leaving no context to
+        // un-current — in that case there is nothing to do.
+        if let Some(gl_context) = self.gl_context.take() {
+            self.gl_context = Some(
+                gl_context
+                    .make_not_current()
+                    .unwrap()
+                    .treat_as_possibly_current(),
+            );
+        }
     }
 
     fn window_event(
@@ -143,23 +255,49 @@ impl ApplicationHandler for App {
                 event_loop.exit();
             }
             WindowEvent::Resized(size) if size.width != 0 && size.height != 0 => {
-                let gl_context = self.gl_context.as_ref().unwrap();
-                let gl_surface = self.gl_surface.as_ref().unwrap();
-                gl_surface.resize(
-                    gl_context,
-                    NonZeroU32::new(size.width).unwrap(),
-                    NonZeroU32::new(size.height).unwrap(),
-                );
+                if let (Some(state), Some(gl_context)) = (&self.state, &self.gl_context) {
+                    // Let `GlWindow::resize_surface` recompute the surface size
+                    // from the window itself, then match the viewport to the size.
+                    state.window.resize_surface(&state.gl_surface, gl_context);
 
-                let renderer = self.renderer.as_ref().unwrap();
-                renderer.resize(size.width as i32, size.height as i32);
+                    let renderer = self.renderer.as_ref().unwrap();
+                    renderer.resize(size.width as i32, size.height as i32);
+                }
             }
             WindowEvent::RedrawRequested => {
-                let gl_surface = self.gl_surface.as_ref().unwrap();
-                let window = self.window.as_ref().unwrap();
+                let Some(state) = self.state.as_ref() else {
+                    return;
+                };
+
+                self.renderer.as_mut().unwrap().poll_hot_reload();
+
+                let gl_surface = &state.gl_surface;
+                let window = &state.window;
                 let gl_context = self.gl_context.as_ref().unwrap();
                 let renderer = self.renderer.as_ref().unwrap();
-                renderer.draw();
+
+                // Pull the freshest decoded frame. If one is ready we draw it as
+                // a textured quad, otherwise fall back to the still-image
+                // overlay (if one was loaded) or the triangle so the window is
+                // never empty while the pipeline pre-rolls.
+                let drew_frame = self
+                    .video
+                    .as_mut()
+                    .and_then(|video| {
+                        video.with_latest_texture(|tex, aspect| renderer.draw_texture(tex, aspect))
+                    })
+                    .is_some();
+
+                if !drew_frame {
+                    match &self.overlay {
+                        Some(overlay) => {
+                            let aspect = overlay.width() as f32 / overlay.height() as f32;
+                            renderer.draw_texture(overlay.raw(), aspect)
+                        }
+                        None => renderer.draw(),
+                    }
+                }
+
                 window.request_redraw();
 
                 gl_surface.swap_buffers(gl_context).unwrap();
@@ -170,109 +308,143 @@ impl ApplicationHandler for App {
     }
 }
 
-use std::ffi::{CStr, CString};
-use std::ops::Deref;
-
-pub mod gl {
-    #![allow(clippy::all)]
-    include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
-}
-
-use gl::types::GLfloat;
+use glow::HasContext as _;
 
 pub struct Renderer {
-    program: gl::types::GLuint,
-    vao: gl::types::GLuint,
-    vbo: gl::types::GLuint,
-    gl: gl::Gl,
+    program: glow::Program,
+    vao: glow::VertexArray,
+    vbo: glow::Buffer,
+    tex_program: glow::Program,
+    tex_vao: glow::VertexArray,
+    tex_vbo: glow::Buffer,
+    tex_sampler: Option<glow::UniformLocation>,
+    tex_scale: Option<glow::UniformLocation>,
+    viewport: Cell<(i32, i32)>,
+    shader_dir: PathBuf,
+    reload_rx: Receiver<()>,
+    _watcher: notify::RecommendedWatcher,
+    gl: glow::Context,
 }
 
 impl Renderer {
-    pub fn new<D: GlDisplay>(gl_display: &D) -> Self {
+    pub fn new<D: GlDisplay>(gl_display: &D) -> Result<Self, ShaderError> {
         unsafe {
-            let gl = gl::Gl::load_with(|symbol| {
-                let symbol = CString::new(symbol).unwrap();
-                gl_display.get_proc_address(symbol.as_c_str()).cast()
+            let gl = glow::Context::from_loader_function_cstr(|symbol| {
+                gl_display.get_proc_address(symbol).cast()
             });
 
-            if let Some(renderer) = get_gl_string(&gl, gl::RENDERER) {
-                println!("Running on {}", renderer.to_string_lossy());
-            }
-            if let Some(version) = get_gl_string(&gl, gl::VERSION) {
-                println!("OpenGL Version {}", version.to_string_lossy());
-            }
-
-            if let Some(shaders_version) = get_gl_string(&gl, gl::SHADING_LANGUAGE_VERSION) {
-                println!("Shaders version on {}", shaders_version.to_string_lossy());
-            }
-
-            let vertex_shader = create_shader(&gl, gl::VERTEX_SHADER, VERTEX_SHADER_SOURCE);
-            let fragment_shader = create_shader(&gl, gl::FRAGMENT_SHADER, FRAGMENT_SHADER_SOURCE);
-
-            let program = gl.CreateProgram();
-
-            gl.AttachShader(program, vertex_shader);
-            gl.AttachShader(program, fragment_shader);
-
-            gl.LinkProgram(program);
-
-            gl.UseProgram(program);
+            println!("Running on {}", gl.get_parameter_string(glow::RENDERER));
+            println!("OpenGL Version {}", gl.get_parameter_string(glow::VERSION));
+            println!(
+                "Shaders version on {}",
+                gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION)
+            );
 
-            gl.DeleteShader(vertex_shader);
-            gl.DeleteShader(fragment_shader);
+            let shader_dir = shader_dir();
+            let program = load_shader_program(&gl, &shader_dir)?;
 
-            let mut vao = std::mem::zeroed();
-            gl.GenVertexArrays(1, &mut vao);
-            gl.BindVertexArray(vao);
+            gl.use_program(Some(program));
 
-            let mut vbo = std::mem::zeroed();
-            gl.GenBuffers(1, &mut vbo);
-            gl.BindBuffer(gl::ARRAY_BUFFER, vbo);
-            gl.BufferData(
-                gl::ARRAY_BUFFER,
-                (VERTEX_DATA.len() * std::mem::size_of::<f32>()) as gl::types::GLsizeiptr,
-                VERTEX_DATA.as_ptr() as *const _,
-                gl::STATIC_DRAW,
+            // Watch the shader directory so edits to `shader.vert`/`shader.frag`
+            // are picked up without restarting; events are drained by `App` in
+            // `window_event` via `poll_hot_reload`.
+            let (tx, reload_rx) = std::sync::mpsc::channel();
+            let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                if let Ok(event) = res {
+                    if event.kind.is_modify() {
+                        let _ = tx.send(());
+                    }
+                }
+            })
+            .expect("failed to create shader watcher");
+            watcher
+                .watch(&shader_dir, RecursiveMode::NonRecursive)
+                .expect("failed to watch shader directory");
+
+            let vao = gl.create_vertex_array().expect("failed to create VAO");
+            gl.bind_vertex_array(Some(vao));
+
+            let vbo = gl.create_buffer().expect("failed to create VBO");
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+            gl.buffer_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                as_bytes(&VERTEX_DATA),
+                glow::STATIC_DRAW,
             );
 
-            // let pos_attrib = gl.GetAttribLocation(program, b"position\0".as_ptr() as *const _);
-            // let color_attrib = gl.GetAttribLocation(program, b"color\0".as_ptr() as *const _);
-            // gl.VertexAttribPointer(
-            //     pos_attrib as gl::types::GLuint,
-            //     2,
-            //     gl::FLOAT,
-            //     0,
-            //     5 * std::mem::size_of::<f32>() as gl::types::GLsizei,
-            //     std::ptr::null(),
-            // );
-
-            gl.VertexAttribPointer(
-                0 as gl::types::GLuint,
-                3,
-                gl::FLOAT,
-                0,
-                3 * std::mem::size_of::<f32>() as gl::types::GLsizei,
-                std::ptr::null(),
+            let pos_stride = 3 * std::mem::size_of::<f32>() as i32;
+            gl.vertex_attrib_pointer_f32(0, 3, glow::FLOAT, false, pos_stride, 0);
+            gl.enable_vertex_attrib_array(0);
+
+            // Texture program: a position + texcoord quad sampled through a
+            // single `sampler2D`, shared by video frames and still images.
+            let tex_program = link_program(
+                &gl,
+                create_shader(&gl, glow::VERTEX_SHADER, TEXTURE_VERTEX_SHADER_SOURCE)?,
+                create_shader(&gl, glow::FRAGMENT_SHADER, TEXTURE_FRAGMENT_SHADER_SOURCE)?,
+            )?;
+
+            let tex_vao = gl.create_vertex_array().expect("failed to create texture VAO");
+            gl.bind_vertex_array(Some(tex_vao));
+
+            let tex_vbo = gl.create_buffer().expect("failed to create texture VBO");
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(tex_vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, as_bytes(&QUAD_DATA), glow::STATIC_DRAW);
+
+            let stride = 4 * std::mem::size_of::<f32>() as i32;
+            gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, stride, 0);
+            gl.enable_vertex_attrib_array(0);
+            gl.vertex_attrib_pointer_f32(
+                1,
+                2,
+                glow::FLOAT,
+                false,
+                stride,
+                2 * std::mem::size_of::<f32>() as i32,
             );
+            gl.enable_vertex_attrib_array(1);
+
+            let tex_sampler = gl.get_uniform_location(tex_program, "uTexture");
+            let tex_scale = gl.get_uniform_location(tex_program, "uScale");
 
-            // gl.VertexAttribPointer(
-            //     color_attrib as gl::types::GLuint,
-            //     3,
-            //     gl::FLOAT,
-            //     0,
-            //     5 * std::mem::size_of::<f32>() as gl::types::GLsizei,
-            //     (2 * std::mem::size_of::<f32>()) as *const () as *const _,
-            // );
-            // gl.EnableVertexAttribArray(pos_attrib as gl::types::GLuint);
-            gl.EnableVertexAttribArray(0 as gl::types::GLuint);
-            // gl.EnableVertexAttribArray(color_attrib as gl::types::GLuint);
-
-            Self {
+            Ok(Self {
                 program,
                 vao,
                 vbo,
+                tex_program,
+                tex_vao,
+                tex_vbo,
+                tex_sampler,
+                tex_scale,
+                viewport: Cell::new((1, 1)),
+                shader_dir,
+                reload_rx,
+                _watcher: watcher,
                 gl,
-            }
+            })
+        }
+    }
+
+    /// Drain pending filesystem events and, if any fired, rebuild the triangle
+    /// program from disk. A failed reload keeps the previous program live and
+    /// logs the read/compile/link error so a typo — or a transient read during
+    /// an atomic-rename save — never crashes the session.
+    pub fn poll_hot_reload(&mut self) {
+        let mut changed = false;
+        while self.reload_rx.try_recv().is_ok() {
+            changed = true;
+        }
+        if !changed {
+            return;
+        }
+
+        match unsafe { load_shader_program(&self.gl, &self.shader_dir) } {
+            Ok(program) => unsafe {
+                self.gl.delete_program(self.program);
+                self.program = program;
+                println!("Reloaded shaders from {}", self.shader_dir.display());
+            },
+            Err(err) => eprintln!("shader reload failed, keeping previous program: {err}"),
         }
     }
 
@@ -280,81 +452,496 @@ impl Renderer {
         self.draw_with_clear_color(0.1, 0.1, 0.1, 0.9)
     }
 
-    pub fn draw_with_clear_color(
-        &self,
-        red: GLfloat,
-        green: GLfloat,
-        blue: GLfloat,
-        alpha: GLfloat,
-    ) {
+    pub fn draw_with_clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
         unsafe {
-            self.gl.UseProgram(self.program);
+            self.gl.use_program(Some(self.program));
 
-            self.gl.BindVertexArray(self.vao);
-            self.gl.BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            self.gl.bind_vertex_array(Some(self.vao));
+            self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
 
-            self.gl.ClearColor(red, green, blue, alpha);
-            self.gl.Clear(gl::COLOR_BUFFER_BIT);
-            self.gl.DrawArrays(gl::TRIANGLES, 0, 3);
+            self.gl.clear_color(red, green, blue, alpha);
+            self.gl.clear(glow::COLOR_BUFFER_BIT);
+            self.gl.draw_arrays(glow::TRIANGLES, 0, 3);
         }
     }
 
-    pub fn resize(&self, width: i32, height: i32) {
+    /// Draw `tex_id` (a `GL_TEXTURE_2D`) as a quad whose aspect matches
+    /// `content_aspect` (width / height of the source image or video frame),
+    /// letterboxing or pillarboxing against the current viewport so the frame is
+    /// never stretched. Black bars fill the leftover area.
+    pub fn draw_texture(&self, tex_id: u32, content_aspect: f32) {
+        let texture = glow::NativeTexture(NonZeroU32::new(tex_id).expect("texture id must be non-zero"));
+        let (scale_x, scale_y) = self.fit_scale(content_aspect);
         unsafe {
-            self.gl.Viewport(0, 0, width, height);
+            self.gl.use_program(Some(self.tex_program));
+
+            self.gl.bind_vertex_array(Some(self.tex_vao));
+            self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.tex_vbo));
+
+            self.gl.active_texture(glow::TEXTURE0);
+            self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            self.gl.uniform_1_i32(self.tex_sampler.as_ref(), 0);
+            self.gl.uniform_2_f32(self.tex_scale.as_ref(), scale_x, scale_y);
+
+            self.gl.clear_color(0.0, 0.0, 0.0, 1.0);
+            self.gl.clear(glow::COLOR_BUFFER_BIT);
+            self.gl.draw_arrays(glow::TRIANGLES, 0, 6);
         }
     }
-}
 
-impl Deref for Renderer {
-    type Target = gl::Gl;
+    /// NDC scale factors that shrink the fullscreen quad so `content_aspect`
+    /// fits inside the current viewport without distortion. The larger axis
+    /// stays at 1.0; the other is pulled in to create the letter/pillarbox.
+    fn fit_scale(&self, content_aspect: f32) -> (f32, f32) {
+        let (width, height) = self.viewport.get();
+        if width <= 0 || height <= 0 || !content_aspect.is_finite() || content_aspect <= 0.0 {
+            return (1.0, 1.0);
+        }
+        let window_aspect = width as f32 / height as f32;
+        if content_aspect > window_aspect {
+            (1.0, window_aspect / content_aspect)
+        } else {
+            (content_aspect / window_aspect, 1.0)
+        }
+    }
+
+    pub fn resize(&self, width: i32, height: i32) {
+        self.viewport.set((width, height));
+        unsafe {
+            self.gl.viewport(0, 0, width, height);
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.gl
+    /// Decode an image from disk and upload it as a `Texture` that
+    /// [`draw_texture`](Self::draw_texture) can render.
+    pub fn load_image(&self, path: &Path) -> Result<Texture, DecodeError> {
+        let (width, height, pixels) = decode_image(path)?;
+        Ok(unsafe { Texture::new(&self.gl, width, height, &pixels) })
     }
 }
 
 impl Drop for Renderer {
     fn drop(&mut self) {
         unsafe {
-            self.gl.DeleteProgram(self.program);
-            self.gl.DeleteBuffers(1, &self.vbo);
-            self.gl.DeleteVertexArrays(1, &self.vao);
+            self.gl.delete_program(self.program);
+            self.gl.delete_buffer(self.vbo);
+            self.gl.delete_vertex_array(self.vao);
+            self.gl.delete_program(self.tex_program);
+            self.gl.delete_buffer(self.tex_vbo);
+            self.gl.delete_vertex_array(self.tex_vao);
+        }
+    }
+}
+
+/// A shader source could not be read, compiled, or linked. Carries the driver's
+/// info log (or the io error) so the failure can be reported instead of showing
+/// a blank window.
+#[derive(Debug)]
+pub enum ShaderError {
+    Read { path: PathBuf, source: std::io::Error },
+    Compile { stage: &'static str, log: String },
+    Link { log: String },
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderError::Read { path, source } => {
+                write!(f, "failed to read shader {}: {source}", path.display())
+            }
+            ShaderError::Compile { stage, log } => {
+                write!(f, "failed to compile {stage} shader:\n{log}")
+            }
+            ShaderError::Link { log } => write!(f, "failed to link program:\n{log}"),
         }
     }
 }
 
+impl std::error::Error for ShaderError {}
+
+fn stage_name(stage: u32) -> &'static str {
+    match stage {
+        glow::VERTEX_SHADER => "vertex",
+        glow::FRAGMENT_SHADER => "fragment",
+        _ => "unknown",
+    }
+}
+
 unsafe fn create_shader(
-    gl: &gl::Gl,
-    shader: gl::types::GLenum,
-    source: &[u8],
-) -> gl::types::GLuint {
-    let shader = unsafe { gl.CreateShader(shader) };
+    gl: &glow::Context,
+    stage: u32,
+    source: &str,
+) -> Result<glow::Shader, ShaderError> {
+    let shader = gl.create_shader(stage).map_err(|log| ShaderError::Compile {
+        stage: stage_name(stage),
+        log,
+    })?;
+    gl.shader_source(shader, source);
+    gl.compile_shader(shader);
+
+    if !gl.get_shader_compile_status(shader) {
+        let log = gl.get_shader_info_log(shader);
+        gl.delete_shader(shader);
+        return Err(ShaderError::Compile {
+            stage: stage_name(stage),
+            log,
+        });
+    }
+
+    Ok(shader)
+}
+
+unsafe fn link_program(
+    gl: &glow::Context,
+    vertex_shader: glow::Shader,
+    fragment_shader: glow::Shader,
+) -> Result<glow::Program, ShaderError> {
+    let program = gl
+        .create_program()
+        .map_err(|log| ShaderError::Link { log })?;
+
+    gl.attach_shader(program, vertex_shader);
+    gl.attach_shader(program, fragment_shader);
+
+    gl.link_program(program);
+
+    gl.delete_shader(vertex_shader);
+    gl.delete_shader(fragment_shader);
+
+    if !gl.get_program_link_status(program) {
+        let log = gl.get_program_info_log(program);
+        gl.delete_program(program);
+        return Err(ShaderError::Link { log });
+    }
+
+    Ok(program)
+}
+
+/// Directory holding the live-reloadable shader sources.
+fn shader_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("shaders")
+}
+
+/// Read a shader source from `dir`. Failures are surfaced as `ShaderError::Read`
+/// so a transient read during an atomic-rename save (editors that truncate or
+/// briefly remove the file) keeps the previous program instead of crashing.
+fn read_shader(dir: &Path, name: &str) -> Result<String, ShaderError> {
+    let path = dir.join(name);
+    std::fs::read_to_string(&path).map_err(|source| ShaderError::Read { path, source })
+}
+
+/// Compile the triangle vertex/fragment shaders from disk and link them.
+unsafe fn load_shader_program(
+    gl: &glow::Context,
+    dir: &Path,
+) -> Result<glow::Program, ShaderError> {
+    let vertex = read_shader(dir, VERTEX_SHADER_FILE)?;
+    let fragment = read_shader(dir, FRAGMENT_SHADER_FILE)?;
+    link_program(
+        gl,
+        create_shader(gl, glow::VERTEX_SHADER, &vertex)?,
+        create_shader(gl, glow::FRAGMENT_SHADER, &fragment)?,
+    )
+}
+
+/// Reinterpret a slice of `f32` vertex data as raw bytes for `buffer_data`.
+fn as_bytes(data: &[f32]) -> &[u8] {
     unsafe {
-        gl.ShaderSource(
-            shader,
-            1,
-            [source.as_ptr().cast()].as_ptr(),
-            std::ptr::null(),
+        std::slice::from_raw_parts(data.as_ptr().cast(), std::mem::size_of_val(data))
+    }
+}
+
+/// A `GL_TEXTURE_2D` holding decoded RGBA8 pixels, used for still-image
+/// overlays (poster art, a paused frame, a "waiting for host" screen).
+pub struct Texture {
+    id: glow::Texture,
+    width: u32,
+    height: u32,
+}
+
+impl Texture {
+    /// Upload `rgba8` (tightly packed, `width * height * 4` bytes) as a texture.
+    pub unsafe fn new(gl: &glow::Context, width: u32, height: u32, rgba8: &[u8]) -> Self {
+        let id = gl.create_texture().expect("failed to create texture");
+        gl.bind_texture(glow::TEXTURE_2D, Some(id));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(Some(rgba8)),
         );
-        gl.CompileShader(shader);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+
+        Self { id, width, height }
+    }
+
+    /// The raw GL name, for passing to [`Renderer::draw_texture`].
+    pub fn raw(&self) -> u32 {
+        self.id.0.get()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
     }
-    shader
 }
 
-fn get_gl_string(gl: &gl::Gl, variant: gl::types::GLenum) -> Option<&'static CStr> {
-    unsafe {
-        let s = gl.GetString(variant);
-        (!s.is_null()).then(|| CStr::from_ptr(s.cast()))
+/// An image file could not be loaded or decoded.
+#[derive(Debug)]
+pub enum DecodeError {
+    Image(image::ImageError),
+    Jxl(jxl_oxide::Error),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Image(err) => write!(f, "{err}"),
+            DecodeError::Jxl(err) => write!(f, "{err}"),
+        }
     }
 }
 
-// // #[rustfmt::skip]
-// // static VERTEX_DATA: [f32; 15] = [
-// //     -0.5, -0.5,  1.0,  0.0,  0.0,
-// //      0.0,  0.5,  0.0,  1.0,  0.0,
-// //      0.5, -0.5,  0.0,  0.0,  1.0,
-// // ];
+impl std::error::Error for DecodeError {}
+
+impl From<image::ImageError> for DecodeError {
+    fn from(err: image::ImageError) -> Self {
+        DecodeError::Image(err)
+    }
+}
+
+impl From<jxl_oxide::Error> for DecodeError {
+    fn from(err: jxl_oxide::Error) -> Self {
+        DecodeError::Jxl(err)
+    }
+}
+
+/// Decode `path` into `(width, height, rgba8)`. JPEG XL goes through
+/// `jxl-oxide`; every other format through the `image` crate (with AVIF
+/// support enabled via the `avif-native` feature).
+fn decode_image(path: &Path) -> Result<(u32, u32, Vec<u8>), DecodeError> {
+    let is_jxl = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jxl"));
+
+    if is_jxl {
+        let image = jxl_oxide::JxlImage::builder().open(path)?;
+        let render = image.render_frame(0)?;
+        let frame = render.image_all_channels();
+        let width = frame.width() as u32;
+        let height = frame.height() as u32;
+        let channels = frame.channels();
+        let buf = frame.buf();
+
+        let to_u8 = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+        for pixel in buf.chunks(channels) {
+            // Fewer than three channels is greyscale, optionally followed by alpha.
+            let (g, b, a) = if channels < 3 {
+                (pixel[0], pixel[0], pixel.get(1))
+            } else {
+                (pixel[1], pixel[2], pixel.get(3))
+            };
+            let r = to_u8(pixel[0]);
+            let a = a.copied().map_or(255, to_u8);
+            rgba.extend_from_slice(&[r, to_u8(g), to_u8(b), a]);
+        }
+        Ok((width, height, rgba))
+    } else {
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+        Ok((width, height, image.into_raw()))
+    }
+}
+
+/// A GStreamer pipeline that decodes a video on the GPU and hands its frames to
+/// the renderer as `GL_TEXTURE_2D` textures, sharing the glutin GL context so no
+/// second GL context is ever created.
+struct VideoPlayer {
+    pipeline: gst::Pipeline,
+    appsink: gst_app::AppSink,
+    gst_context: gst_gl::GLContext,
+    sample: Option<gst::Sample>,
+}
+
+impl VideoPlayer {
+    /// Build a player for `uri` through `playbin`, or — when no URI is given —
+    /// a built-in `videotestsrc ! glupload` test pattern so the window shows
+    /// something even without `WATCH_TOGETHER_URI` set.
+    fn new<D>(gl_context: &PossiblyCurrentContext, gl_display: &D, uri: Option<&str>) -> Self
+    where
+        D: GlDisplay + AsRawDisplay,
+    {
+        // Wrap the existing glutin display + context so GStreamer uploads into
+        // the very textures we later sample from.
+        let (gst_display, platform) = wrap_gl_display(gl_display);
+        let raw_handle = match gl_context.raw_context() {
+            #[cfg(egl)]
+            RawContext::Egl(handle) => handle as usize,
+            #[cfg(glx)]
+            RawContext::Glx(handle) => handle as usize,
+            #[cfg(wgl)]
+            RawContext::Wgl(handle) => handle as usize,
+            _ => panic!("unsupported GL context for GStreamer sharing"),
+        };
+
+        let gst_context = unsafe {
+            gst_gl::GLContext::new_wrapped(
+                &gst_display,
+                raw_handle,
+                platform,
+                gst_gl::GLAPI::OPENGL3,
+            )
+        }
+        .expect("failed to wrap GL context");
+        gst_context.activate(true).unwrap();
+        gst_context.fill_info().unwrap();
+
+        let appsink = gst_app::AppSink::builder()
+            .caps(
+                &gst_video::VideoCapsBuilder::new()
+                    .features([gst_gl::CAPS_FEATURE_MEMORY_GL_MEMORY])
+                    .format(gst_video::VideoFormat::Rgba)
+                    .field("texture-target", "2D")
+                    .build(),
+            )
+            .max_buffers(1)
+            .drop(true)
+            .build();
+
+        let pipeline = match uri {
+            Some(uri) => {
+                let glsinkbin = gst::ElementFactory::make("glsinkbin")
+                    .property("sink", &appsink)
+                    .build()
+                    .unwrap();
+
+                gst::ElementFactory::make("playbin")
+                    .property("uri", uri)
+                    .property("video-sink", &glsinkbin)
+                    .build()
+                    .unwrap()
+                    .downcast::<gst::Pipeline>()
+                    .unwrap()
+            }
+            None => {
+                let src = gst::ElementFactory::make("videotestsrc").build().unwrap();
+                let upload = gst::ElementFactory::make("glupload").build().unwrap();
+                let convert = gst::ElementFactory::make("glcolorconvert").build().unwrap();
+
+                let pipeline = gst::Pipeline::new();
+                pipeline
+                    .add_many([&src, &upload, &convert, appsink.upcast_ref::<gst::Element>()])
+                    .unwrap();
+                gst::Element::link_many([&src, &upload, &convert, appsink.upcast_ref::<gst::Element>()]).unwrap();
+                pipeline
+            }
+        };
+
+        // Hand the shared display + context back to the pipeline when it asks.
+        let bus = pipeline.bus().unwrap();
+        let display_clone = gst_display.clone();
+        let context_clone = gst_context.clone();
+        bus.set_sync_handler(move |_, msg| {
+            if let gst::MessageView::NeedContext(ctx) = msg.view() {
+                let ctx_type = ctx.context_type();
+                if let Some(element) = msg.src().and_then(|s| s.downcast_ref::<gst::Element>()) {
+                    if ctx_type == *gst_gl::GL_DISPLAY_CONTEXT_TYPE {
+                        let context = gst::Context::new(ctx_type, true);
+                        context.set_gl_display(&display_clone);
+                        element.set_context(&context);
+                    } else if ctx_type == "gst.gl.app_context" {
+                        let mut context = gst::Context::new(ctx_type, true);
+                        {
+                            let context = context.get_mut().unwrap();
+                            context.structure_mut().set("context", &context_clone);
+                        }
+                        element.set_context(&context);
+                    }
+                }
+            }
+            gst::BusSyncReply::Pass
+        });
+
+        pipeline.set_state(gst::State::Playing).unwrap();
+
+        Self {
+            pipeline,
+            appsink,
+            gst_context,
+            sample: None,
+        }
+    }
+
+    /// Make the freshest decoded frame current and invoke `draw` with its
+    /// texture id and display aspect ratio (width / height, corrected for the
+    /// stream's pixel aspect ratio), waiting on the frame's `GLSyncMeta` first.
+    /// Returns `None` when no frame is available yet.
+    fn with_latest_texture<R>(&mut self, draw: impl FnOnce(u32, f32) -> R) -> Option<R> {
+        if let Some(sample) = self.appsink.try_pull_sample(gst::ClockTime::ZERO) {
+            self.sample = Some(sample);
+        }
+
+        let sample = self.sample.as_ref()?;
+        let buffer = sample.buffer()?;
+        let info = gst_video::VideoInfo::from_caps(&sample.caps()?).ok()?;
+
+        // Wait on the fence the upstream gl element already inserted on the
+        // GStreamer thread so the upload is complete before we sample — do not
+        // insert a fresh sync point here, which would overwrite it.
+        if let Some(sync_meta) = buffer.meta::<gst_gl::GLSyncMeta>() {
+            sync_meta.wait(&self.gst_context);
+        }
+
+        let par = info.par();
+        let aspect = (info.width() as f32 * par.numer() as f32)
+            / (info.height() as f32 * par.denom() as f32);
+
+        let frame = gst_gl::GLVideoFrame::from_buffer_readable(buffer.to_owned(), &info).ok()?;
+        let tex_id = frame.texture_id(0).ok()?;
+
+        Some(draw(tex_id, aspect))
+    }
+}
+
+impl Drop for VideoPlayer {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gst::State::Null);
+    }
+}
+
+/// Wrap a glutin `GlDisplay` into the matching `gst_gl::GLDisplay`.
+fn wrap_gl_display<D: AsRawDisplay>(gl_display: &D) -> (gst_gl::GLDisplay, gst_gl::GLPlatform) {
+    match gl_display.raw_display() {
+        #[cfg(egl)]
+        RawDisplay::Egl(egl_display) => {
+            let display = unsafe {
+                gst_gl_egl::GLDisplayEGL::with_egl_display(egl_display as usize).unwrap()
+            };
+            (display.upcast(), gst_gl::GLPlatform::EGL)
+        }
+        #[cfg(glx)]
+        RawDisplay::Glx(glx_display) => {
+            let display = unsafe {
+                gst_gl_x11::GLDisplayX11::with_display(glx_display as usize).unwrap()
+            };
+            (display.upcast(), gst_gl::GLPlatform::GLX)
+        }
+        #[cfg(wgl)]
+        RawDisplay::Wgl(_) => (gst_gl::GLDisplay::new(), gst_gl::GLPlatform::WGL),
+        _ => (gst_gl::GLDisplay::new(), gst_gl::GLPlatform::empty()),
+    }
+}
 
 #[rustfmt::skip]
 static VERTEX_DATA: [f32; 9] = [
@@ -363,48 +950,45 @@ static VERTEX_DATA: [f32; 9] = [
      0.0,  0.5, 0.0
 ];
 
-// // const VERTEX_SHADER_SOURCE: &[u8] = b"
-// // #version 100
-// // precision mediump float;
+#[rustfmt::skip]
+static QUAD_DATA: [f32; 24] = [
+    // position   texcoord
+    -1.0, -1.0,   0.0, 1.0,
+     1.0, -1.0,   1.0, 1.0,
+     1.0,  1.0,   1.0, 0.0,
+    -1.0, -1.0,   0.0, 1.0,
+     1.0,  1.0,   1.0, 0.0,
+    -1.0,  1.0,   0.0, 0.0,
+];
 
-// // attribute vec2 position;
-// // attribute vec3 color;
+const VERTEX_SHADER_FILE: &str = "shader.vert";
+const FRAGMENT_SHADER_FILE: &str = "shader.frag";
 
-// // varying vec3 v_color;
+const TEXTURE_VERTEX_SHADER_SOURCE: &str = "\
+#version 460 core
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec2 aTexCoord;
 
-// // void main() {
-// //     gl_Position = vec4(position, 0.0, 1.0);
-// //     v_color = color;
-// // }
-// // \0";
+uniform vec2 uScale;
 
-const VERTEX_SHADER_SOURCE: &[u8] = b"
-#version 460 core
-layout (location = 0) in vec3 aPos;
+out vec2 vTexCoord;
 
 void main()
 {
-    gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0);
+    gl_Position = vec4(aPos * uScale, 0.0, 1.0);
+    vTexCoord = aTexCoord;
 }
-\0";
-
-// // const FRAGMENT_SHADER_SOURCE: &[u8] = b"
-// // #version 100
-// // precision mediump float;
+";
 
-// // varying vec3 v_color;
-
-// // void main() {
-// //     gl_FragColor = vec4(v_color, 1.0);
-// // }
-// // \0";
-
-const FRAGMENT_SHADER_SOURCE: &[u8] = b"
+const TEXTURE_FRAGMENT_SHADER_SOURCE: &str = "\
 #version 460 core
+in vec2 vTexCoord;
 out vec4 FragColor;
 
+uniform sampler2D uTexture;
+
 void main()
 {
-    FragColor = vec4(1.0f, 1.0f, 0.2f, 1.0f);
+    FragColor = texture(uTexture, vTexCoord);
 }
-\0";
+";